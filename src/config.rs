@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A single bech32-prefix -> LCD endpoint mapping.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainEntry {
+    pub prefix: String,
+    pub lcd: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ChainConfig {
+    #[serde(default)]
+    chains: Vec<ChainEntry>,
+}
+
+const DEFAULT_CHAINS: &[(&str, &str)] = &[
+    ("ki", "https://api-mainnet.blockchain.ki"),
+    ("tki", "https://api-challenge.blockchain.ki"),
+    ("juno", "https://api-juno-ia.cosmosia.notional.ventures/"),
+    ("osmo", "https://lcd.osmosis.zone/"),
+    ("chihuahua", "https://api.chihuahua.wtf/"),
+    ("stars", "https://rest.stargaze-apis.com/"),
+];
+
+fn default_chains() -> Vec<ChainEntry> {
+    DEFAULT_CHAINS
+        .iter()
+        .map(|(prefix, lcd)| ChainEntry {
+            prefix: prefix.to_string(),
+            lcd: lcd.to_string(),
+        })
+        .collect()
+}
+
+fn config_path() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "gaal")
+        .context("could not determine a config directory for this platform")?;
+    Ok(dirs.config_dir().join("chains.toml"))
+}
+
+/// Load the user's chain registry, merging it ahead of the built-in defaults.
+///
+/// On first run, no config file exists yet, so we write one out containing the
+/// current defaults to give users a starting point to edit.
+pub fn load_chains() -> Result<Vec<ChainEntry>> {
+    let path = config_path()?;
+
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create config directory {}", parent.display())
+            })?;
+        }
+        let defaults = ChainConfig {
+            chains: default_chains(),
+        };
+        let serialized =
+            toml::to_string_pretty(&defaults).context("failed to serialize default chain config")?;
+        fs::write(&path, serialized).with_context(|| {
+            format!("failed to write default chain config at {}", path.display())
+        })?;
+        return Ok(defaults.chains);
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read chain config at {}", path.display()))?;
+    let mut chains = toml::from_str::<ChainConfig>(&contents)
+        .with_context(|| format!("failed to parse chain config at {}", path.display()))?
+        .chains;
+    chains.extend(default_chains());
+    Ok(chains)
+}
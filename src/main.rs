@@ -1,21 +1,16 @@
-use anyhow::bail;
+use anyhow::anyhow;
 
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use std::{
-    env, io,
-    time::{Duration, Instant},
-};
+use crossbeam_channel::{never, select, tick, unbounded, Receiver};
+use std::{collections::VecDeque, env, io, time::Duration};
+use std::thread;
 use synnax::cosmos::Cosmos;
 use synnax::lcd::Lcd;
 use synnax::query::contract::{Contract, ItemOrMap};
+use term_backend::Key;
 use tui::layout::Alignment;
 use tui::widgets::Paragraph;
 use tui::{
-    backend::{Backend, CrosstermBackend},
+    backend::Backend,
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
@@ -23,22 +18,67 @@ use tui::{
     Frame, Terminal,
 };
 
-fn find_chain_by_prefix(contract_address: String) -> Result<String, anyhow::Error> {
-    Ok(if contract_address.starts_with("ki") {
-        String::from("https://api-mainnet.blockchain.ki")
-    } else if contract_address.starts_with("tki") {
-        String::from("https://api-challenge.blockchain.ki")
-    } else if contract_address.starts_with("juno") {
-        String::from("https://api-juno-ia.cosmosia.notional.ventures/")
-    } else if contract_address.starts_with("osmo") {
-        String::from("https://lcd.osmosis.zone/")
-    } else if contract_address.starts_with("chihuahua") {
-        String::from("https://api.chihuahua.wtf/")
-    } else if contract_address.starts_with("stars") {
-        String::from("https://rest.stargaze-apis.com/")
-    } else {
-        bail!("Invalid bech32 address => {}", contract_address);
-    })
+mod config;
+mod term_backend;
+
+/// How many freshly-arrived keys to fold into the visible list per tick.
+const KEY_BATCH_SIZE: usize = 25;
+
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+fn find_chain_by_prefix(
+    contract_address: &str,
+    chains: &[config::ChainEntry],
+) -> Result<String, anyhow::Error> {
+    chains
+        .iter()
+        .find(|chain| contract_address.starts_with(chain.prefix.as_str()))
+        .map(|chain| chain.lcd.clone())
+        .ok_or_else(|| {
+            let known = chains
+                .iter()
+                .map(|chain| chain.prefix.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow!(
+                "Invalid bech32 address => {} (known prefixes: {})",
+                contract_address,
+                known
+            )
+        })
+}
+
+/// Result of the background contract fetch, sent back to the render loop.
+enum LoadMessage {
+    Loaded(Box<Contract>),
+    Failed(String),
+}
+
+/// Fetch the contract state on a background thread so the UI can paint immediately.
+fn spawn_contract_loader(lcd: Lcd, address: String) -> Receiver<LoadMessage> {
+    let (tx, rx) = unbounded();
+    thread::spawn(move || {
+        let cosmos = Cosmos::new(&lcd);
+        let message = match Contract::new(cosmos, address) {
+            Ok(contract) => LoadMessage::Loaded(Box::new(contract)),
+            Err(err) => LoadMessage::Failed(err.to_string()),
+        };
+        let _ = tx.send(message);
+    });
+    rx
+}
+
+/// Make sure a panic inside the render loop (e.g. a stray `unwrap()`) doesn't leave the
+/// user's shell stuck in raw mode / the alternate screen. Restores the terminal first,
+/// then chains to whatever hook was previously installed so the backtrace still prints.
+/// Under the `termion` backend the restore step does nothing: the terminal is restored
+/// only when termion's guards drop during unwinding, after the previous hook has run.
+fn install_panic_hook() {
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        term_backend::restore_terminal_on_panic();
+        original_hook(panic_info);
+    }));
 }
 
 fn main() -> Result<(), anyhow::Error> {
@@ -51,36 +91,28 @@ fn main() -> Result<(), anyhow::Error> {
         return Ok(());
     }
 
-    let address = &args[1];
+    let address = args[1].clone();
 
+    let chains = config::load_chains()?;
     let lcd = Lcd::new(if let Ok(lcd) = std::env::var("OVERLOAD_LCD") {
         lcd
     } else {
-        find_chain_by_prefix(address.clone())?
+        find_chain_by_prefix(&address, &chains)?
     })?;
-    let cosmos = Cosmos::new(&lcd);
-    let contract = Contract::new(cosmos, address.clone())?;
 
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    install_panic_hook();
+
+    let mut terminal = term_backend::setup_terminal()?;
 
-    // create app and run it
+    // create app and run it, fetching the contract state in the background so the
+    // UI paints and stays interactive instead of freezing on the initial HTTP call.
     let tick_rate = Duration::from_millis(250);
-    let app = App::new(&contract);
-    let res = run_app(&mut terminal, app, tick_rate);
-
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    let input_rx = term_backend::spawn_input_thread();
+    let state_rx = spawn_contract_loader(lcd, address);
+    let app = App::new();
+    let res = run_app(&mut terminal, app, tick_rate, input_rx, state_rx);
+
+    term_backend::teardown_terminal(terminal)?;
 
     if let Err(err) = res {
         println!("{:?}", err)
@@ -95,51 +127,119 @@ enum ListType {
     MapKeyList,
 }
 
-struct StatefulList<'a> {
+/// Whether the key list is navigating normally or capturing a filter query.
+#[derive(PartialEq)]
+enum InputMode {
+    Normal,
+    Editing,
+}
+
+struct StatefulList {
     state: ListState,
     items: Vec<String>,
     second_state: ListState,
     second_items: Vec<String>,
-    contract: &'a Contract,
     current_list: ListType,
+    query: String,
+    // Visible row -> real index into `items`/`second_items`, kept in sync with `query`.
+    filtered_items: Vec<usize>,
+    filtered_second_items: Vec<usize>,
 }
 
-impl<'a> StatefulList<'a> {
-    fn update_second_list(&mut self) {
-        let value = self
-            .contract
-            .state
-            .get(self.items[self.state.selected().unwrap()].as_str())
-            .unwrap();
-
-        if let ItemOrMap::Map { map } = value {
-            self.second_items = Vec::from_iter(map.keys().cloned());
-            self.second_state.select(Some(0usize));
-        } else {
-            self.second_state.select(None);
-            self.second_items.clear();
-        };
-    }
-
-    fn with_items(items: Vec<String>, contract: &'a Contract) -> StatefulList {
-        let mut list = StatefulList {
+impl StatefulList {
+    fn new() -> StatefulList {
+        StatefulList {
             state: ListState::default(),
-            items,
+            items: vec![],
             second_state: ListState::default(),
             second_items: vec![],
-            contract,
             current_list: ListType::StateKeyList,
-        };
-        list.state.select(Some(0usize));
-        list.update_second_list();
+            query: String::new(),
+            filtered_items: vec![],
+            filtered_second_items: vec![],
+        }
+    }
+
+    fn selected_key(&self) -> Option<&str> {
+        self.state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+            .map(|&idx| self.items[idx].as_str())
+    }
+
+    fn update_second_list(&mut self, contract: &Contract) {
+        self.second_items.clear();
+
+        if let Some(key) = self.selected_key() {
+            if let Some(ItemOrMap::Map { map }) = contract.state.get(key) {
+                self.second_items = Vec::from_iter(map.keys().cloned());
+            }
+        }
 
-        list
+        self.filter_second_items();
     }
 
-    fn next(&mut self) {
+    fn filter_second_items(&mut self) {
+        let query = self.query.to_lowercase();
+        self.filtered_second_items = self
+            .second_items
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| query.is_empty() || key.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect();
+
+        self.second_state
+            .select(if self.filtered_second_items.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+    }
+
+    fn apply_filter(&mut self) {
+        let needle = self.query.to_lowercase();
+        self.filtered_items = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| needle.is_empty() || key.to_lowercase().contains(&needle))
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Rebuild the filtered view of both lists against `query` and jump back to row 0.
+    fn set_query(&mut self, query: String, contract: &Contract) {
+        self.query = query;
+        self.apply_filter();
+        self.state.select(if self.filtered_items.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.update_second_list(contract);
+    }
+
+    /// Replace `items` (e.g. with a newly-arrived batch of keys), keeping the current
+    /// selection where it's still valid instead of jumping back to row 0.
+    fn set_items(&mut self, items: Vec<String>, contract: &Contract) {
+        self.items = items;
+        self.apply_filter();
+        let selection_still_valid = matches!(self.state.selected(), Some(i) if i < self.filtered_items.len());
+        if !selection_still_valid {
+            self.state.select(if self.filtered_items.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        }
+        self.update_second_list(contract);
+    }
+
+    fn next(&mut self, contract: &Contract) {
         let (list_len, state, refresh_key) = match self.current_list {
-            ListType::StateKeyList => (self.items.len(), &mut self.state, true),
-            ListType::MapKeyList => (self.second_items.len(), &mut self.second_state, false),
+            ListType::StateKeyList => (self.filtered_items.len(), &mut self.state, true),
+            ListType::MapKeyList => (self.filtered_second_items.len(), &mut self.second_state, false),
         };
 
         if list_len == 0 {
@@ -160,16 +260,21 @@ impl<'a> StatefulList<'a> {
         state.select(Some(i));
 
         if refresh_key {
-            self.update_second_list();
+            self.update_second_list(contract);
         }
     }
 
-    fn previous(&mut self) {
+    fn previous(&mut self, contract: &Contract) {
         let (list_len, state, refresh_key) = match self.current_list {
-            ListType::StateKeyList => (self.items.len(), &mut self.state, true),
-            ListType::MapKeyList => (self.second_items.len(), &mut self.second_state, false),
+            ListType::StateKeyList => (self.filtered_items.len(), &mut self.state, true),
+            ListType::MapKeyList => (self.filtered_second_items.len(), &mut self.second_state, false),
         };
 
+        if list_len == 0 {
+            state.select(None);
+            return;
+        }
+
         let i = match state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -182,12 +287,12 @@ impl<'a> StatefulList<'a> {
         };
         state.select(Some(i));
         if refresh_key {
-            self.update_second_list();
+            self.update_second_list(contract);
         }
     }
 
     fn go_right(&mut self) {
-        if self.current_list == ListType::StateKeyList && !self.second_items.is_empty() {
+        if self.current_list == ListType::StateKeyList && !self.filtered_second_items.is_empty() {
             self.current_list = ListType::MapKeyList;
         }
     }
@@ -199,28 +304,110 @@ impl<'a> StatefulList<'a> {
     }
 }
 
-struct App<'a> {
-    items: StatefulList<'a>,
-    events: Vec<(&'a str, &'a str)>,
-    contract: &'a Contract,
+struct App {
+    items: StatefulList,
+    contract: Option<Contract>,
+    pending_keys: VecDeque<String>,
+    load_error: Option<String>,
+    input_mode: InputMode,
+    query: String,
+    // Snapshot of `query` taken on entering Editing, restored if the user presses Esc.
+    query_before_edit: String,
+    tick_count: u64,
 }
 
-impl<'a> App<'a> {
-    fn new(contract: &'a Contract) -> App<'a> {
-        let keys = contract.state.keys().cloned().collect();
-
+impl App {
+    fn new() -> App {
         App {
-            items: StatefulList::with_items(keys, contract),
-            events: vec![("No", "Value")],
-            contract,
+            items: StatefulList::new(),
+            contract: None,
+            pending_keys: VecDeque::new(),
+            load_error: None,
+            input_mode: InputMode::Normal,
+            query: String::new(),
+            query_before_edit: String::new(),
+            tick_count: 0,
         }
     }
 
-    /// Rotate through the event list.
-    /// This only exists to simulate some kind of "progress"
+    fn is_loading(&self) -> bool {
+        self.contract.is_none() || !self.pending_keys.is_empty()
+    }
+
+    /// The contract has finished loading; queue its keys to be folded into the
+    /// visible list a batch at a time instead of all at once.
+    fn begin_loading(&mut self, contract: Contract) {
+        self.pending_keys = contract.state.keys().cloned().collect();
+        self.contract = Some(contract);
+        // A query typed while the contract was still loading never reached
+        // `StatefulList`, since `refresh_query` was a no-op with no contract to
+        // filter against. Sync it now so the title bar and the visible list agree.
+        self.refresh_query();
+    }
+
+    /// Advance the spinner and fold in the next batch of pending keys, if any.
     fn on_tick(&mut self) {
-        let event = self.events.remove(0);
-        self.events.push(event);
+        self.tick_count = self.tick_count.wrapping_add(1);
+
+        if self.pending_keys.is_empty() {
+            return;
+        }
+        let contract = match self.contract.as_ref() {
+            Some(contract) => contract,
+            None => return,
+        };
+
+        let mut items = std::mem::take(&mut self.items.items);
+        for _ in 0..KEY_BATCH_SIZE {
+            match self.pending_keys.pop_front() {
+                Some(key) => items.push(key),
+                None => break,
+            }
+        }
+        self.items.set_items(items, contract);
+    }
+
+    fn move_next(&mut self) {
+        if let Some(contract) = self.contract.as_ref() {
+            self.items.next(contract);
+        }
+    }
+
+    fn move_previous(&mut self) {
+        if let Some(contract) = self.contract.as_ref() {
+            self.items.previous(contract);
+        }
+    }
+
+    fn enter_editing(&mut self) {
+        self.query_before_edit = self.query.clone();
+        self.input_mode = InputMode::Editing;
+    }
+
+    fn cancel_editing(&mut self) {
+        self.query = self.query_before_edit.clone();
+        self.refresh_query();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn confirm_editing(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn push_query_char(&mut self, c: char) {
+        self.query.push(c);
+        self.refresh_query();
+    }
+
+    fn pop_query_char(&mut self) {
+        self.query.pop();
+        self.refresh_query();
+    }
+
+    fn refresh_query(&mut self) {
+        if let Some(contract) = self.contract.as_ref() {
+            self.items.set_query(self.query.clone(), contract);
+        }
     }
 }
 
@@ -228,29 +415,54 @@ fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
     tick_rate: Duration,
+    input_rx: Receiver<Key>,
+    state_rx: Receiver<LoadMessage>,
 ) -> io::Result<()> {
-    let mut last_tick = Instant::now();
+    let ticker = tick(tick_rate);
+    let mut input_rx = input_rx;
+    let mut state_rx = state_rx;
+
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Left => app.items.go_left(),
-                    KeyCode::Right => app.items.go_right(),
-                    KeyCode::Down => app.items.next(),
-                    KeyCode::Up => app.items.previous(),
-                    _ => {}
+        select! {
+            recv(input_rx) -> key => {
+                match key {
+                    Ok(key) => match app.input_mode {
+                        InputMode::Normal => match key {
+                            Key::Char('q') => return Ok(()),
+                            Key::Char('/') | Key::Char('i') => app.enter_editing(),
+                            Key::Left => app.items.go_left(),
+                            Key::Right => app.items.go_right(),
+                            Key::Down => app.move_next(),
+                            Key::Up => app.move_previous(),
+                            _ => {}
+                        },
+                        InputMode::Editing => match key {
+                            Key::Enter => app.confirm_editing(),
+                            Key::Esc => app.cancel_editing(),
+                            Key::Char(c) => app.push_query_char(c),
+                            Key::Backspace => app.pop_query_char(),
+                            _ => {}
+                        },
+                    },
+                    // The input thread died (e.g. stdin isn't a tty); there's no way
+                    // left to drive the UI, so stop selecting on it rather than
+                    // busy-spinning on a disconnected channel.
+                    Err(_) => input_rx = never(),
                 }
             }
-        }
-        if last_tick.elapsed() >= tick_rate {
-            app.on_tick();
-            last_tick = Instant::now();
+            recv(state_rx) -> message => {
+                match message {
+                    Ok(LoadMessage::Loaded(contract)) => app.begin_loading(*contract),
+                    Ok(LoadMessage::Failed(err)) => app.load_error = Some(err),
+                    Err(_) => {}
+                }
+                // The loader thread only ever sends once; stop selecting on it so we
+                // don't spin on a disconnected channel for the rest of the session.
+                state_rx = never();
+            }
+            recv(ticker) -> _ => app.on_tick(),
         }
     }
 }
@@ -266,20 +478,33 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
         .split(global_panel[1]);
 
-    // Iterate through all elements in the `items` app and append some debug text to it.
+    // Build one row per entry of the filtered view, resolving each back to its key in `items`.
     let items: Vec<ListItem> = app
         .items
-        .items
+        .filtered_items
         .iter()
-        .map(|i| {
-            let lines = vec![Spans::from(i.as_str())];
+        .map(|&idx| {
+            let lines = vec![Spans::from(app.items.items[idx].as_str())];
             ListItem::new(lines).style(Style::default().fg(Color::Gray))
         })
         .collect();
 
+    let state_key_title = if let Some(err) = &app.load_error {
+        format!("State key [error: {}]", err)
+    } else if app.is_loading() {
+        let frame = SPINNER_FRAMES[(app.tick_count as usize) % SPINNER_FRAMES.len()];
+        format!("State key [{} loading...]", frame)
+    } else {
+        match app.input_mode {
+            InputMode::Editing => format!("State key [/{}]", app.query),
+            InputMode::Normal if !app.query.is_empty() => format!("State key [{}]", app.query),
+            InputMode::Normal => "State key".to_string(),
+        }
+    };
+
     // Create a List from all list items and highlight the currently selected one
     let items = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("State key"))
+        .block(Block::default().borders(Borders::ALL).title(state_key_title))
         .highlight_style(
             Style::default()
                 .bg(match app.items.current_list {
@@ -298,10 +523,10 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // The event list doesn't have any state and only displays the current state of the list.
     let second_key: Vec<ListItem> = app
         .items
-        .second_items
+        .filtered_second_items
         .iter()
-        .map(|i| {
-            let lines = vec![Spans::from(i.as_str())];
+        .map(|&idx| {
+            let lines = vec![Spans::from(app.items.second_items[idx].as_str())];
             ListItem::new(lines).style(Style::default().fg(Color::Gray))
         })
         .collect();
@@ -323,23 +548,28 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let paragraph = Paragraph::new(Spans::from(Span::styled(
         match app.items.state.selected() {
             None => "NO KEY SELECTED",
-            Some(idx) => {
-                let value = app
-                    .contract
-                    .state
-                    .get(app.items.items[idx].as_str())
-                    .unwrap();
-
-                match value {
-                    ItemOrMap::Item { value } => value.as_str(),
-                    ItemOrMap::Map { map } => map
-                        .get(
-                            app.items.second_items[app.items.second_state.selected().unwrap()]
-                                .as_str(),
-                        )
-                        .unwrap(),
+            Some(idx) => match app
+                .items
+                .items
+                .get(app.items.filtered_items[idx])
+                .and_then(|key| app.contract.as_ref().and_then(|c| c.state.get(key)))
+            {
+                None => "NO VALUE",
+                Some(ItemOrMap::Item { value }) => value.as_str(),
+                Some(ItemOrMap::Map { map }) => {
+                    let second_key = app
+                        .items
+                        .second_state
+                        .selected()
+                        .and_then(|i| app.items.filtered_second_items.get(i))
+                        .and_then(|&idx| app.items.second_items.get(idx));
+
+                    match second_key.and_then(|key| map.get(key.as_str())) {
+                        Some(value) => value.as_str(),
+                        None => "EMPTY MAP",
+                    }
                 }
-            }
+            },
         },
         Style::default().add_modifier(Modifier::ITALIC),
     )))
@@ -0,0 +1,69 @@
+use super::Key;
+use crossbeam_channel::{unbounded, Receiver};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::{self, Stdout};
+use std::thread;
+use tui::backend::CrosstermBackend;
+use tui::Terminal;
+
+pub type AppBackend = CrosstermBackend<Stdout>;
+
+pub fn setup_terminal() -> Result<Terminal<AppBackend>, anyhow::Error> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+pub fn teardown_terminal(mut terminal: Terminal<AppBackend>) -> Result<(), anyhow::Error> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Best-effort terminal restoration for a panic hook; errors are swallowed since we're
+/// already unwinding.
+pub fn restore_terminal_on_panic() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+}
+
+/// Forward terminal events onto a channel so the render loop can `select!` on them
+/// alongside the contract loader and the tick timer, instead of blocking on them.
+pub fn spawn_input_thread() -> Receiver<Key> {
+    let (tx, rx) = unbounded();
+    thread::spawn(move || loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) => map_key(key.code),
+            Ok(_) => continue,
+            Err(_) => break,
+        };
+        if tx.send(key).is_err() {
+            break;
+        }
+    });
+    rx
+}
+
+fn map_key(code: KeyCode) -> Key {
+    match code {
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Char(c) => Key::Char(c),
+        _ => Key::Other,
+    }
+}
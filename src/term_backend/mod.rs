@@ -0,0 +1,30 @@
+//! Terminal backend abstraction.
+//!
+//! `run_app`'s navigation logic only needs a small, backend-agnostic `Key` enum and a
+//! way to set up / tear down the terminal; the actual backend (crossterm, termion, ...)
+//! is picked at compile time via cargo features, one module per backend, mirroring how
+//! the `tui` ecosystem ships one demo per backend.
+
+#[cfg(feature = "termion")]
+mod termion_backend;
+#[cfg(feature = "termion")]
+pub use termion_backend::*;
+
+#[cfg(not(feature = "termion"))]
+mod crossterm_backend;
+#[cfg(not(feature = "termion"))]
+pub use crossterm_backend::*;
+
+/// Backend-agnostic key, mapped from whatever the active `tui` backend delivers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Enter,
+    Esc,
+    Backspace,
+    Char(char),
+    Other,
+}
@@ -0,0 +1,63 @@
+use super::Key;
+use crossbeam_channel::{unbounded, Receiver};
+use std::io::{self, Stdout};
+use std::thread;
+use termion::event::Key as TermionKey;
+use termion::input::{MouseTerminal, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::{AlternateScreen, IntoAlternateScreen};
+use tui::backend::TermionBackend;
+use tui::Terminal;
+
+pub type AppBackend = TermionBackend<AlternateScreen<MouseTerminal<RawTerminal<Stdout>>>>;
+
+pub fn setup_terminal() -> Result<Terminal<AppBackend>, anyhow::Error> {
+    let stdout = io::stdout().into_raw_mode()?;
+    let stdout = MouseTerminal::from(stdout);
+    let stdout = stdout.into_alternate_screen()?;
+    Ok(Terminal::new(TermionBackend::new(stdout))?)
+}
+
+/// Takes `terminal` by value so dropping it here - deterministically, before this
+/// function returns - is what leaves the alternate screen, disables mouse capture, and
+/// restores the original terminal mode. Taking it by reference would leave all of that
+/// to whenever `terminal` happens to go out of scope in the caller (e.g. after `main`
+/// has already printed a run error into the still-active alternate screen).
+pub fn teardown_terminal(mut terminal: Terminal<AppBackend>) -> Result<(), anyhow::Error> {
+    terminal.show_cursor()?;
+    drop(terminal);
+    Ok(())
+}
+
+/// Does nothing. Termion restores raw mode / the alternate screen only when its guards
+/// drop during unwinding, which happens after the panic hook (and the backtrace it
+/// prints) has run.
+pub fn restore_terminal_on_panic() {}
+
+/// Forward terminal events onto a channel so the render loop can `select!` on them
+/// alongside the contract loader and the tick timer, instead of blocking on them.
+pub fn spawn_input_thread() -> Receiver<Key> {
+    let (tx, rx) = unbounded();
+    thread::spawn(move || {
+        for key in io::stdin().keys().flatten() {
+            if tx.send(map_key(key)).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn map_key(key: TermionKey) -> Key {
+    match key {
+        TermionKey::Left => Key::Left,
+        TermionKey::Right => Key::Right,
+        TermionKey::Up => Key::Up,
+        TermionKey::Down => Key::Down,
+        TermionKey::Char('\n') => Key::Enter,
+        TermionKey::Esc => Key::Esc,
+        TermionKey::Backspace => Key::Backspace,
+        TermionKey::Char(c) => Key::Char(c),
+        _ => Key::Other,
+    }
+}